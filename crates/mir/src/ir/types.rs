@@ -1,8 +1,11 @@
+use std::collections::HashMap;
+
 use fe_analyzer::namespace::items as analyzer_items;
 use fe_common::{impl_intern_key, Span};
+use serde::{Deserialize, Serialize};
 use smol_str::SmolStr;
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Type {
     I8,
     I16,
@@ -28,44 +31,555 @@ pub enum Type {
 }
 
 /// An interned Id for [`ArrayDef`].
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TypeId(u32);
 impl_intern_key!(TypeId);
 
 /// A static array type definition.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ArrayDef {
     pub elem_ty: TypeId,
     pub len: usize,
 }
 
 /// A tuple type definition.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TupleDef {
     pub items: Vec<TypeId>,
 }
 
+/// The visibility of a [`StructDef`] or [`EventDef`] field. Following the
+/// Rust RFC that made named struct fields private by default, a field is
+/// only visible outside its declaring module when explicitly marked `pub`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Visibility {
+    Private,
+    Public,
+}
+
+impl Visibility {
+    /// Whether code in `accessing_module` may read or write a field with
+    /// this visibility, declared in `declaring_module`. This is the check
+    /// [`StructDef::inaccessible_fields_for`] and
+    /// [`EventDef::inaccessible_fields_for`] run per field; the analyzer's
+    /// resolver should call one of those for every field access it resolves
+    /// and emit a deny-level diagnostic for each name they return.
+    pub fn is_accessible_from(
+        &self,
+        accessing_module: analyzer_items::ModuleId,
+        declaring_module: analyzer_items::ModuleId,
+    ) -> bool {
+        matches!(self, Visibility::Public) || accessing_module == declaring_module
+    }
+}
+
+/// A single field of a [`StructDef`], with its own span so go-to-def and
+/// find-references can point at the field itself rather than the whole
+/// struct.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct StructField {
+    pub name: SmolStr,
+    /// `GlobalTypeId` rather than a plain `TypeId` so a field can name a
+    /// type defined in a dependency, not just one interned locally.
+    pub ty: GlobalTypeId,
+    pub visibility: Visibility,
+    pub span: Span,
+}
+
 /// A user defined struct type definition.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct StructDef {
     pub name: SmolStr,
-    pub fields: Vec<(SmolStr, TypeId)>,
+    pub fields: Vec<StructField>,
     pub span: Span,
     pub module_id: analyzer_items::ModuleId,
 }
 
+impl StructDef {
+    /// The name of every field `accessing_module` may not read or write,
+    /// because it's `Private` and declared in a different module than
+    /// `self.module_id`.
+    pub fn inaccessible_fields_for(&self, accessing_module: analyzer_items::ModuleId) -> Vec<&SmolStr> {
+        self.fields
+            .iter()
+            .filter(|field| !field.visibility.is_accessible_from(accessing_module, self.module_id))
+            .map(|field| &field.name)
+            .collect()
+    }
+}
+
+/// A single field of an [`EventDef`], with its own span for the same
+/// go-to-def/find-references reason as [`StructField`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct EventField {
+    pub name: SmolStr,
+    pub ty: GlobalTypeId,
+    /// `true` when this field is an indexed topic, `false` when it's part
+    /// of the log data.
+    pub indexed: bool,
+    pub visibility: Visibility,
+    pub span: Span,
+}
+
 /// A user defined struct type definition.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct EventDef {
     pub name: SmolStr,
-    pub fields: Vec<(SmolStr, TypeId, bool)>,
+    pub fields: Vec<EventField>,
     pub span: Span,
     pub module_id: analyzer_items::ModuleId,
 }
 
-/// A map type definition.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+impl EventDef {
+    /// The name of every field `accessing_module` may not read or write.
+    /// See [`StructDef::inaccessible_fields_for`].
+    pub fn inaccessible_fields_for(&self, accessing_module: analyzer_items::ModuleId) -> Vec<&SmolStr> {
+        self.fields
+            .iter()
+            .filter(|field| !field.visibility.is_accessible_from(accessing_module, self.module_id))
+            .map(|field| &field.name)
+            .collect()
+    }
+}
+
+/// A map type definition. Key/value types are `GlobalTypeId` for the same
+/// cross-crate reason as [`StructDef`] fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct MapDef {
-    pub key_ty: TypeId,
-    pub value_ty: TypeId,
+    pub key_ty: GlobalTypeId,
+    pub value_ty: GlobalTypeId,
+}
+
+/// The on-disk schema version of [`TypeTable`]. Bump this whenever a change
+/// to `Type` or any type it transitively references would make an older
+/// serialized blob unreadable (or silently misread) by a newer consumer.
+pub const FORMAT_VERSION: u32 = 6;
+
+/// Discriminates the compilation unit a [`TypeId`] was interned in. Borrows
+/// the `krate` half of rustc's `DefId { krate, index }`: `TypeId` alone is a
+/// single flat intern space that's only meaningful within one crate, so
+/// crossing a package boundary requires pairing it with a `CrateId`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CrateId(pub u32);
+
+/// The `CrateId` of the crate currently being compiled. Every `TypeId`
+/// minted locally implicitly belongs to this crate.
+pub const LOCAL_CRATE: CrateId = CrateId(0);
+
+/// A `TypeId` paired with the crate it was interned in, so it can be
+/// resolved across separately compiled Fe packages via [`CrateGraph`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct GlobalTypeId {
+    pub krate: CrateId,
+    pub local: TypeId,
+}
+
+impl GlobalTypeId {
+    /// Builds the global id for a `TypeId` interned by the crate currently
+    /// being compiled.
+    pub fn local(local: TypeId) -> Self {
+        Self {
+            krate: LOCAL_CRATE,
+            local,
+        }
+    }
+
+    /// Returns the local `TypeId` if this id was interned by `krate`,
+    /// `None` if it names a type from another crate.
+    pub fn local_to(&self, krate: CrateId) -> Option<TypeId> {
+        (self.krate == krate).then_some(self.local)
+    }
+}
+
+/// The kind of item an [`ItemSummary`] describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ItemKind {
+    Struct,
+    Event,
+    Contract,
+}
+
+/// A fully-qualified path summary for a user-defined type, mirroring
+/// rustdoc's `paths` map.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ItemSummary {
+    /// The path segments from crate root to item name, e.g.
+    /// `["my_crate", "foo", "Bar"]`.
+    pub path: Vec<SmolStr>,
+    pub kind: ItemKind,
+}
+
+/// A self-contained, serializable snapshot of the lowered `Type` graph,
+/// built by [`TypeTable::build`] by walking the intern pool from a set of
+/// roots. Round-trips through `serde_json` as-is. Nothing in this crate
+/// calls `TypeTable::build` or serializes one yet — there's no driver/CLI
+/// crate in this tree to do it from — so treat JSON export as implemented
+/// but not yet wired up to any caller.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypeTable {
+    /// The schema version this table was serialized with.
+    pub format_version: u32,
+    /// The crate this table's `TypeId`s were interned by.
+    pub krate: CrateId,
+    /// Every `TypeId` reachable from `roots`, resolved to its `Type`.
+    pub index: HashMap<TypeId, Type>,
+    /// The contract/struct/event entry points that consumers should start
+    /// traversal from.
+    pub roots: Vec<TypeId>,
+    /// The fully-qualified path of every `TypeId` in `index` that resolves
+    /// to a `StructDef`, `EventDef`, or `Contract`, keyed the same way as
+    /// `index` so consumers can look either up from the same id.
+    /// [`TypeTable::build`] does not fill this map: it stays empty unless
+    /// entries are inserted into it directly.
+    pub paths: HashMap<TypeId, ItemSummary>,
+}
+
+impl TypeTable {
+    /// Creates an empty table for [`LOCAL_CRATE`], stamped with the current
+    /// [`FORMAT_VERSION`].
+    pub fn new() -> Self {
+        Self {
+            format_version: FORMAT_VERSION,
+            krate: LOCAL_CRATE,
+            index: HashMap::new(),
+            roots: Vec::new(),
+            paths: HashMap::new(),
+        }
+    }
+}
+
+impl Default for TypeTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Type {
+    /// Every `TypeId` this type directly references *within the current
+    /// crate*, e.g. a struct's field types or an array's element type. Used
+    /// by [`TypeTable::build`] to walk the local intern pool; a field or
+    /// `MapDef` half naming a foreign crate's type is skipped here and must
+    /// instead be followed through a [`CrateGraph`].
+    fn referenced_type_ids(&self) -> Vec<TypeId> {
+        match self {
+            Type::Array(def) => vec![def.elem_ty],
+            Type::Tuple(def) => def.items.clone(),
+            Type::Struct(def) | Type::Contract(def) => def
+                .fields
+                .iter()
+                .filter_map(|field| field.ty.local_to(LOCAL_CRATE))
+                .collect(),
+            Type::Event(def) => def
+                .fields
+                .iter()
+                .filter_map(|field| field.ty.local_to(LOCAL_CRATE))
+                .collect(),
+            Type::Map(def) => [def.key_ty, def.value_ty]
+                .into_iter()
+                .filter_map(|id| id.local_to(LOCAL_CRATE))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Minimal database surface needed to resolve a `TypeId` to its interned
+/// `Type`. Implemented by the compiler's query-group database.
+pub trait TypeInternDb {
+    fn lookup_intern_type(&self, id: TypeId) -> Type;
+}
+
+impl TypeTable {
+    /// Builds a self-contained table by resolving every `TypeId`
+    /// transitively reachable from `roots` through `db`, so the result needs
+    /// no further lookups against the intern pool. TODO(driver-crate): wire
+    /// this up to an actual CLI entry point once one exists; there's no
+    /// caller for it in this tree yet.
+    pub fn build(db: &dyn TypeInternDb, roots: Vec<TypeId>) -> Self {
+        let mut table = Self {
+            roots: roots.clone(),
+            ..Self::new()
+        };
+        let mut stack = roots;
+        while let Some(id) = stack.pop() {
+            if table.index.contains_key(&id) {
+                continue;
+            }
+            let ty = db.lookup_intern_type(id);
+            stack.extend(ty.referenced_type_ids());
+            table.index.insert(id, ty);
+        }
+        table
+    }
+}
+
+/// This crate's own [`TypeTable`] plus one per dependency, keyed by
+/// [`CrateId`]. `StructDef`/`EventDef`/`MapDef` fields hold a
+/// [`GlobalTypeId`] directly, so resolving one just means looking it up
+/// here.
+#[derive(Debug, Clone, Default)]
+pub struct CrateGraph {
+    pub tables: HashMap<CrateId, TypeTable>,
+}
+
+impl CrateGraph {
+    /// Resolves a `GlobalTypeId` to its `Type`, following into the owning
+    /// crate's table. Returns `None` if `krate` isn't in this graph or
+    /// `local` isn't interned in that crate's table.
+    pub fn resolve(&self, id: GlobalTypeId) -> Option<&Type> {
+        self.tables.get(&id.krate)?.index.get(&id.local)
+    }
+}
+
+/// Trait implemented by the compiler's query-group database, kept minimal
+/// here so this module can resolve a [`TypeId`] to its [`ItemSummary`]
+/// without depending on the full database trait.
+pub trait TypePathDb {
+    /// Returns the `ItemSummary` for `id`, or `None` if `id` doesn't resolve
+    /// to a `StructDef`, `EventDef`, or `Contract`.
+    fn type_item_summary(&self, id: TypeId) -> Option<ItemSummary>;
+}
+
+/// Minimal database surface needed to walk a `ModuleId`'s parent chain.
+/// Implemented by the same query-group database that answers
+/// `TypeInternDb::lookup_intern_type`.
+pub trait ModulePathDb {
+    /// This module's own name, e.g. `"foo"` for `src/foo.fe`. Never queried
+    /// for the crate root module.
+    fn module_name(&self, module: analyzer_items::ModuleId) -> SmolStr;
+    /// This module's parent, or `None` if it's the crate root.
+    fn module_parent(&self, module: analyzer_items::ModuleId) -> Option<analyzer_items::ModuleId>;
+    /// The name of the crate `module` belongs to.
+    fn crate_name(&self, module: analyzer_items::ModuleId) -> SmolStr;
+}
+
+/// Computes an `ItemSummary` for any `TypeId` that resolves to a
+/// `StructDef`, `EventDef`, or `Contract`, by walking its `module_id`'s
+/// parent chain up to the crate root. Blanket-implemented for any database
+/// that can do both lookups, so `TypeId::qualified_path` works off a single
+/// `&dyn TypePathDb` without the caller juggling two traits.
+impl<D: TypeInternDb + ModulePathDb> TypePathDb for D {
+    fn type_item_summary(&self, id: TypeId) -> Option<ItemSummary> {
+        let (name, module_id, kind) = match self.lookup_intern_type(id) {
+            Type::Struct(def) => (def.name, def.module_id, ItemKind::Struct),
+            Type::Contract(def) => (def.name, def.module_id, ItemKind::Contract),
+            Type::Event(def) => (def.name, def.module_id, ItemKind::Event),
+            _ => return None,
+        };
+
+        let mut path = vec![name];
+        let mut current = Some(module_id);
+        while let Some(module) = current {
+            match self.module_parent(module) {
+                Some(parent) => {
+                    path.push(self.module_name(module));
+                    current = Some(parent);
+                }
+                None => current = None,
+            }
+        }
+        path.push(self.crate_name(module_id));
+        path.reverse();
+
+        Some(ItemSummary { path, kind })
+    }
+}
+
+impl TypeId {
+    /// Returns the fully-qualified path of the item this id resolves to,
+    /// e.g. `my_crate::foo::Bar`, or `<type#N>` with `N` the raw index of
+    /// `self` if it doesn't name a `StructDef`, `EventDef`, or `Contract`.
+    pub fn qualified_path(&self, db: &dyn TypePathDb) -> SmolStr {
+        match db.type_item_summary(*self) {
+            Some(summary) => SmolStr::new(summary.path.join("::")),
+            None => SmolStr::new(format!("<type#{}>", self.0)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FakeDb(HashMap<TypeId, Type>);
+
+    impl TypeInternDb for FakeDb {
+        fn lookup_intern_type(&self, id: TypeId) -> Type {
+            self.0.get(&id).cloned().expect("id not interned")
+        }
+    }
+
+    #[test]
+    fn build_walks_the_intern_pool() {
+        let leaf = TypeId(0);
+        let root = TypeId(1);
+        let mut pool = HashMap::new();
+        pool.insert(leaf, Type::U256);
+        pool.insert(root, Type::Tuple(TupleDef { items: vec![leaf] }));
+        let db = FakeDb(pool);
+
+        let table = TypeTable::build(&db, vec![root]);
+
+        assert_eq!(table.roots, vec![root]);
+        assert_eq!(table.index.len(), 2);
+        assert_eq!(table.index[&leaf], Type::U256);
+        assert_eq!(table.index[&root], Type::Tuple(TupleDef { items: vec![leaf] }));
+    }
+
+    #[test]
+    fn type_table_round_trips_through_json() {
+        let mut table = TypeTable::new();
+        table.roots.push(TypeId(0));
+        table.index.insert(TypeId(0), Type::Bool);
+
+        let json = serde_json::to_string(&table).unwrap();
+        let back: TypeTable = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(back.roots, table.roots);
+        assert_eq!(back.index, table.index);
+    }
+
+    #[test]
+    fn visibility_is_accessible_from() {
+        use salsa::InternKey;
+
+        let declaring = analyzer_items::ModuleId::from_intern_id(salsa::InternId::from(0u32));
+        let same_module = declaring;
+        let other_module = analyzer_items::ModuleId::from_intern_id(salsa::InternId::from(1u32));
+
+        assert!(Visibility::Public.is_accessible_from(other_module, declaring));
+        assert!(Visibility::Private.is_accessible_from(same_module, declaring));
+        assert!(!Visibility::Private.is_accessible_from(other_module, declaring));
+    }
+
+    #[test]
+    fn struct_def_reports_its_inaccessible_fields() {
+        use salsa::InternKey;
+
+        let declaring_module = analyzer_items::ModuleId::from_intern_id(salsa::InternId::from(0u32));
+        let other_module = analyzer_items::ModuleId::from_intern_id(salsa::InternId::from(1u32));
+        let field_ty = GlobalTypeId::local(TypeId::from_intern_id(salsa::InternId::from(0u32)));
+
+        let struct_def = StructDef {
+            name: "Foo".into(),
+            fields: vec![
+                StructField {
+                    name: "secret".into(),
+                    ty: field_ty,
+                    visibility: Visibility::Private,
+                    span: Span::default(),
+                },
+                StructField {
+                    name: "public_field".into(),
+                    ty: field_ty,
+                    visibility: Visibility::Public,
+                    span: Span::default(),
+                },
+            ],
+            span: Span::default(),
+            module_id: declaring_module,
+        };
+
+        assert_eq!(
+            struct_def.inaccessible_fields_for(declaring_module),
+            Vec::<&SmolStr>::new()
+        );
+        assert_eq!(
+            struct_def.inaccessible_fields_for(other_module),
+            vec![&SmolStr::new("secret")]
+        );
+    }
+
+    struct FakeModuleDb {
+        types: HashMap<TypeId, Type>,
+        names: HashMap<analyzer_items::ModuleId, SmolStr>,
+        parents: HashMap<analyzer_items::ModuleId, analyzer_items::ModuleId>,
+        crate_name: SmolStr,
+    }
+
+    impl TypeInternDb for FakeModuleDb {
+        fn lookup_intern_type(&self, id: TypeId) -> Type {
+            self.types.get(&id).cloned().expect("id not interned")
+        }
+    }
+
+    impl ModulePathDb for FakeModuleDb {
+        fn module_name(&self, module: analyzer_items::ModuleId) -> SmolStr {
+            self.names.get(&module).cloned().expect("module not named")
+        }
+
+        fn module_parent(&self, module: analyzer_items::ModuleId) -> Option<analyzer_items::ModuleId> {
+            self.parents.get(&module).copied()
+        }
+
+        fn crate_name(&self, _module: analyzer_items::ModuleId) -> SmolStr {
+            self.crate_name.clone()
+        }
+    }
+
+    #[test]
+    fn qualified_path_walks_the_module_chain() {
+        use salsa::InternKey;
+
+        let root_module = analyzer_items::ModuleId::from_intern_id(salsa::InternId::from(0u32));
+        let foo_module = analyzer_items::ModuleId::from_intern_id(salsa::InternId::from(1u32));
+        let struct_id = TypeId::from_intern_id(salsa::InternId::from(0u32));
+
+        let struct_def = StructDef {
+            name: "Bar".into(),
+            fields: vec![],
+            span: Span::default(),
+            module_id: foo_module,
+        };
+
+        let mut types = HashMap::new();
+        types.insert(struct_id, Type::Struct(struct_def));
+
+        let mut names = HashMap::new();
+        names.insert(foo_module, SmolStr::new("foo"));
+
+        let mut parents = HashMap::new();
+        parents.insert(foo_module, root_module);
+
+        let db = FakeModuleDb {
+            types,
+            names,
+            parents,
+            crate_name: SmolStr::new("my_crate"),
+        };
+
+        assert_eq!(struct_id.qualified_path(&db).as_str(), "my_crate::foo::Bar");
+    }
+
+    #[test]
+    fn crate_graph_resolves_a_struct_field_from_a_dependency() {
+        use salsa::InternKey;
+
+        let dep_crate = CrateId(7);
+        let dep_ty = TypeId::from_intern_id(salsa::InternId::from(0u32));
+        let mut dep_table = TypeTable::new();
+        dep_table.krate = dep_crate;
+        dep_table.index.insert(dep_ty, Type::U256);
+
+        let mut graph = CrateGraph::default();
+        graph.tables.insert(dep_crate, dep_table);
+
+        let struct_def = StructDef {
+            name: "Foo".into(),
+            fields: vec![StructField {
+                name: "amount".into(),
+                ty: GlobalTypeId {
+                    krate: dep_crate,
+                    local: dep_ty,
+                },
+                visibility: Visibility::Public,
+                span: Span::default(),
+            }],
+            span: Span::default(),
+            module_id: analyzer_items::ModuleId::default(),
+        };
+
+        let field_ty = struct_def.fields[0].ty;
+        assert_eq!(graph.resolve(field_ty), Some(&Type::U256));
+    }
 }